@@ -1,8 +1,10 @@
-use futures::future::{join_all, try_join_all};
+use base64::Engine;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use futures::future::join_all;
 use indexmap::IndexMap;
 use log::info;
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::commands::async_command::{async_command, AsyncCallResult, With};
@@ -10,9 +12,12 @@ use serde::{Deserialize, Serialize};
 use tauri::{Manager, State, Window};
 use tauri_plugin_dialog::DialogExt;
 use tokio::fs::write;
+use tokio::sync::Semaphore;
 use url::Url;
 use vrc_get_vpm::environment::{
-    add_remote_repo, clear_package_cache, AddUserPackageResult, Settings, UserPackageCollection,
+    add_remote_repo, clear_package_cache, export_user_package_version,
+    verify_one_package_cache_entry, AddUserPackageResult, PackageCacheVerifyStatus, Settings,
+    UserPackageCollection,
 };
 use vrc_get_vpm::io::{DefaultEnvironmentIo, IoTrait};
 use vrc_get_vpm::repositories_file::RepositoriesFile;
@@ -60,6 +65,135 @@ impl TauriPackage {
     }
 }
 
+#[derive(Serialize, Deserialize, Default)]
+struct RepositoryCacheValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+fn validators_cache_path(local_path: &Path) -> PathBuf {
+    local_path.with_extension("validators.json")
+}
+
+async fn load_cached_validators(
+    io: &DefaultEnvironmentIo,
+    local_path: &Path,
+) -> RepositoryCacheValidators {
+    io.read_to_string(&validators_cache_path(local_path))
+        .await
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+async fn save_cached_validators(
+    io: &DefaultEnvironmentIo,
+    local_path: &Path,
+    validators: &RepositoryCacheValidators,
+) {
+    if let Ok(json) = serde_json::to_vec(validators) {
+        let _ = io.write(&validators_cache_path(local_path), &json).await;
+    }
+}
+
+// Re-downloads one repository's cached listing, sending the previous ETag /
+// Last-Modified validators so an unchanged repository short-circuits on a
+// 304. When `public_key` is set, re-verifies the response before it's
+// written to disk, so a refetch can never downgrade an already-trusted
+// repository to unverified content. Returns whether the cache was rewritten.
+#[allow(clippy::too_many_arguments)]
+async fn refetch_repository_cache(
+    http: &reqwest::Client,
+    io: &DefaultEnvironmentIo,
+    url: &Url,
+    headers: &IndexMap<Box<str>, Box<str>>,
+    public_key: Option<&str>,
+    local_path: &Path,
+) -> Result<bool, RustError> {
+    let validators = load_cached_validators(io, local_path).await;
+
+    let mut request = http.get(url.clone());
+    for (name, value) in headers {
+        request = request.header(name.as_ref(), value.as_ref());
+    }
+    if let Some(etag) = &validators.etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| RustError::unrecoverable(e.to_string()))?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(false);
+    }
+
+    let response = response
+        .error_for_status()
+        .map_err(|e| RustError::unrecoverable(e.to_string()))?;
+
+    let new_validators = RepositoryCacheValidators {
+        etag: response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned),
+        last_modified: response
+            .headers()
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned),
+    };
+
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| RustError::unrecoverable(e.to_string()))?;
+
+    if let Some(public_key) = public_key {
+        verify_repository_signature(http, url, headers, &body, public_key)
+            .await
+            .map_err(RustError::unrecoverable)?;
+    }
+
+    io.write(local_path, &body).await?;
+    save_cached_validators(io, local_path, &new_validators).await;
+
+    Ok(true)
+}
+
+// Mirrors the curated/official entries `user_repo_urls`/`user_repo_ids` inject
+// separately, since they aren't part of `get_user_repos()` and would
+// otherwise silently stop being refetched.
+fn curated_and_official_refetch_targets(settings: &Settings) -> Vec<(Url, PathBuf)> {
+    let mut targets = Vec::new();
+
+    if !settings.ignore_curated_repository() {
+        targets.push((
+            "https://packages.vrchat.com/curated?download"
+                .parse()
+                .unwrap(),
+            PathBuf::from("Repos/vrc-curated.json"),
+        ));
+    }
+
+    if !settings.ignore_official_repository() {
+        targets.push((
+            "https://packages.vrchat.com/official?download"
+                .parse()
+                .unwrap(),
+            PathBuf::from("Repos/vrc-official.json"),
+        ));
+    }
+
+    targets
+}
+
+// Returns whether any repository listing actually changed on disk.
 #[tauri::command]
 #[specta::specta]
 pub async fn environment_refetch_packages(
@@ -67,13 +201,51 @@ pub async fn environment_refetch_packages(
     settings: State<'_, SettingsState>,
     io: State<'_, DefaultEnvironmentIo>,
     http: State<'_, reqwest::Client>,
-) -> Result<(), RustError> {
+) -> Result<bool, RustError> {
     let settings = settings.load(io.inner()).await?;
-    packages
-        .load_force(&settings, io.inner(), http.inner())
-        .await?;
 
-    Ok(())
+    let mut changed = false;
+    for repo in settings.get_user_repos() {
+        let Some(url) = repo.url() else {
+            // local-file repositories have nothing to conditionally refetch
+            continue;
+        };
+
+        if refetch_repository_cache(
+            http.inner(),
+            io.inner(),
+            url,
+            repo.headers(),
+            repo.public_key(),
+            repo.local_path(),
+        )
+        .await?
+        {
+            changed = true;
+        }
+    }
+
+    for (url, local_path) in curated_and_official_refetch_targets(&settings) {
+        if refetch_repository_cache(
+            http.inner(),
+            io.inner(),
+            &url,
+            &IndexMap::new(),
+            None,
+            &local_path,
+        )
+        .await?
+        {
+            changed = true;
+        }
+    }
+
+    // reload from the (possibly just-updated) on-disk cache instead of
+    // unconditionally re-downloading everything again
+    packages.clear_cache();
+    packages.load(&settings, io.inner(), http.inner()).await?;
+
+    Ok(changed)
 }
 
 #[tauri::command]
@@ -196,6 +368,7 @@ pub enum TauriDownloadRepository {
     BadUrl,
     Duplicated,
     DownloadError { message: String },
+    SignatureError { message: String },
     Success { value: TauriRemoteRepositoryInfo },
 }
 
@@ -209,6 +382,7 @@ pub async fn environment_download_repository(
     http: State<'_, reqwest::Client>,
     url: String,
     headers: IndexMapV2<Box<str>, Box<str>>,
+    public_key: Option<String>,
 ) -> Result<TauriDownloadRepository, RustError> {
     let url: Url = match url.parse() {
         Err(_) => {
@@ -223,9 +397,11 @@ pub async fn environment_download_repository(
         let user_repo_ids = user_repo_ids(&settings);
 
         download_one_repository(
+            http.inner(),
             http.inner(),
             &url,
             &headers.0,
+            public_key.as_deref(),
             &user_repo_urls,
             &user_repo_ids,
         )
@@ -274,8 +450,10 @@ fn user_repo_ids(settings: &Settings) -> HashSet<String> {
 
 async fn download_one_repository(
     client: &impl HttpClient,
+    raw_http: &reqwest::Client,
     repository_url: &Url,
     headers: &IndexMap<Box<str>, Box<str>>,
+    public_key: Option<&str>,
     user_repo_urls: &HashSet<String>,
     user_repo_ids: &HashSet<String>,
 ) -> Result<TauriDownloadRepository, RustError> {
@@ -283,8 +461,8 @@ async fn download_one_repository(
         return Ok(TauriDownloadRepository::Duplicated);
     }
 
-    let repo = match RemoteRepository::download(client, repository_url, headers).await {
-        Ok((repo, _)) => repo,
+    let (repo, raw) = match RemoteRepository::download(client, repository_url, headers).await {
+        Ok(x) => x,
         Err(e) => {
             return Ok(TauriDownloadRepository::DownloadError {
                 message: e.to_string(),
@@ -292,6 +470,14 @@ async fn download_one_repository(
         }
     };
 
+    if let Some(public_key) = public_key {
+        if let Err(message) =
+            verify_repository_signature(raw_http, repository_url, headers, &raw, public_key).await
+        {
+            return Ok(TauriDownloadRepository::SignatureError { message });
+        }
+    }
+
     let url = repo.url().unwrap_or(repository_url).as_str();
     let id = repo.id().unwrap_or(url);
 
@@ -314,6 +500,92 @@ async fn download_one_repository(
     })
 }
 
+// Keeps one failing download from aborting the rest of a bulk import.
+fn download_result_or_error(
+    result: Result<TauriDownloadRepository, RustError>,
+) -> TauriDownloadRepository {
+    result.unwrap_or_else(|e| TauriDownloadRepository::DownloadError {
+        message: e.to_string(),
+    })
+}
+
+// Verifies the exact bytes the server returned (`raw`), not a re-serialized
+// struct, so field ordering can never make a correctly signed listing fail.
+async fn verify_repository_signature(
+    raw_http: &reqwest::Client,
+    repository_url: &Url,
+    headers: &IndexMap<Box<str>, Box<str>>,
+    raw: &[u8],
+    public_key_base64: &str,
+) -> Result<(), String> {
+    let verifying_key = decode_verifying_key(public_key_base64)
+        .map_err(|e| format!("invalid pinned public key for repository: {e}"))?;
+
+    let signature_base64 = fetch_detached_signature(raw_http, repository_url, headers)
+        .await
+        .ok_or_else(|| "repository has a pinned key but no signature was found".to_owned())?;
+
+    let signature_bytes = base64::engine::general_purpose::STANDARD
+        .decode(signature_base64.as_bytes())
+        .map_err(|e| format!("invalid signature encoding: {e}"))?;
+    let signature =
+        Signature::from_slice(&signature_bytes).map_err(|e| format!("malformed signature: {e}"))?;
+
+    verifying_key
+        .verify(raw, &signature)
+        .map_err(|_| "repository signature verification failed".to_owned())
+}
+
+fn decode_verifying_key(public_key_base64: &str) -> Result<VerifyingKey, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(public_key_base64.as_bytes())
+        .map_err(|e| e.to_string())?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| "public key must be 32 bytes".to_owned())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| e.to_string())
+}
+
+async fn fetch_detached_signature(
+    raw_http: &reqwest::Client,
+    repository_url: &Url,
+    headers: &IndexMap<Box<str>, Box<str>>,
+) -> Option<String> {
+    let mut signature_url = repository_url.clone();
+    signature_url.set_path(&format!("{}.sig", signature_url.path()));
+
+    let mut request = raw_http.get(signature_url);
+    for (name, value) in headers {
+        request = request.header(name.as_ref(), value.as_ref());
+    }
+
+    let response = request.send().await.ok()?.error_for_status().ok()?;
+    Some(response.text().await.ok()?.trim().to_owned())
+}
+
+// Pre-fetches and verifies a repository before it's handed to
+// `add_remote_repo`, so the bytes persisted to disk are guaranteed to be the
+// exact bytes that were signature-checked rather than whatever a second,
+// independent download happens to return.
+async fn download_and_verify_for_add(
+    http: &reqwest::Client,
+    url: &Url,
+    headers: &IndexMap<Box<str>, Box<str>>,
+    public_key: Option<&str>,
+) -> Result<Option<Vec<u8>>, RustError> {
+    let Some(public_key) = public_key else {
+        return Ok(None);
+    };
+
+    let (repo, raw) = RemoteRepository::download(http, url, headers).await?;
+    verify_repository_signature(http, url, headers, &raw, public_key)
+        .await
+        .map_err(RustError::unrecoverable)?;
+    drop(repo);
+
+    Ok(Some(raw))
+}
+
 #[derive(Serialize, specta::Type)]
 pub enum TauriAddRepositoryResult {
     BadUrl,
@@ -329,6 +601,7 @@ pub async fn environment_add_repository(
     http: State<'_, reqwest::Client>,
     url: String,
     headers: IndexMapV2<Box<str>, Box<str>>,
+    public_key: Option<String>,
 ) -> Result<TauriAddRepositoryResult, RustError> {
     let url: Url = match url.parse() {
         Err(_) => {
@@ -338,11 +611,23 @@ pub async fn environment_add_repository(
     };
 
     let mut settings = settings.load_mut(io.inner()).await?;
+
+    if public_key.is_none() && settings.require_signed_repositories() {
+        return Err(RustError::unrecoverable(
+            "this environment requires new repositories to be signed",
+        ));
+    }
+
+    let verified_raw =
+        download_and_verify_for_add(http.inner(), &url, &headers.0, public_key.as_deref()).await?;
+
     add_remote_repo(
         &mut settings,
         url,
         None,
         headers.0,
+        public_key,
+        verified_raw,
         io.inner(),
         http.inner(),
     )
@@ -398,6 +683,9 @@ type Headers = IndexMapV2<Box<str>, Box<str>>;
 pub struct TauriRepositoryDescriptor {
     pub url: Url,
     pub headers: Headers,
+    // base64-encoded ed25519 public key pinned for this repository, if any.
+    #[serde(default)]
+    pub public_key: Option<String>,
 }
 
 #[tauri::command]
@@ -422,12 +710,16 @@ pub async fn environment_import_repository_pick(
             .map(|x| TauriRepositoryDescriptor {
                 url: x.url().clone(),
                 headers: IndexMapV2(x.headers().clone()),
+                public_key: None,
             })
             .collect(),
         unparsable_lines: result.unparseable_lines().to_vec(),
     })
 }
 
+// Used when the user hasn't configured `import_repositories_concurrency`.
+const DEFAULT_IMPORT_DOWNLOAD_CONCURRENCY: usize = 5;
+
 #[tauri::command]
 #[specta::specta]
 pub async fn environment_import_download_repositories(
@@ -442,43 +734,65 @@ pub async fn environment_import_download_repositories(
         With::<usize>::continue_async(|ctx| async move {
             let settings = window.state::<SettingsState>();
             let io = window.state::<DefaultEnvironmentIo>();
+            let config = window.state::<GuiConfigState>();
             let settings = settings.load(io.inner()).await?;
             {
                 let user_repo_urls = user_repo_urls(&settings);
                 let mut user_repo_ids = user_repo_ids(&settings);
                 drop(settings);
 
-                info!("downloading {} repositories", repositories.len());
+                let concurrency = config
+                    .get()
+                    .import_repositories_concurrency
+                    .unwrap_or(DEFAULT_IMPORT_DOWNLOAD_CONCURRENCY)
+                    .max(1);
+                drop(config);
+
+                info!(
+                    "downloading {} repositories with concurrency {concurrency}",
+                    repositories.len()
+                );
 
                 let counter = AtomicUsize::new(0);
 
                 let counter_ref = &counter;
                 let user_repo_urls_ref = &user_repo_urls;
                 let user_repo_ids_ref = &user_repo_ids;
+                let semaphore = Semaphore::new(concurrency);
+                let semaphore_ref = &semaphore;
 
                 let http = window.state::<reqwest::Client>();
-                let mut results = try_join_all(repositories.into_iter().map(|adding_repo| {
+                let mut results = join_all(repositories.into_iter().map(|adding_repo| {
                     let ctx = ctx.clone();
                     let http = http.clone();
                     async move {
-                        let downloaded = download_one_repository(
-                            http.inner(),
-                            &adding_repo.url,
-                            &adding_repo.headers.0,
-                            user_repo_urls_ref,
-                            user_repo_ids_ref,
-                        )
-                        .await?;
+                        let _permit = semaphore_ref
+                            .acquire()
+                            .await
+                            .expect("semaphore is never closed");
+
+                        let downloaded = download_result_or_error(
+                            download_one_repository(
+                                http.inner(),
+                                http.inner(),
+                                &adding_repo.url,
+                                &adding_repo.headers.0,
+                                adding_repo.public_key.as_deref(),
+                                user_repo_urls_ref,
+                                user_repo_ids_ref,
+                            )
+                            .await,
+                        );
 
                         info!("downloaded repository: {:?}", adding_repo.url);
 
                         let count = counter_ref.fetch_add(1, Ordering::Relaxed);
                         ctx.emit(count).unwrap();
 
-                        Ok::<_, RustError>((adding_repo, downloaded))
+                        (adding_repo, downloaded)
                     }
                 }))
-                .await?;
+                .await;
 
                 for (_, downloaded) in results.as_mut_slice() {
                     if let TauriDownloadRepository::Success { value } = &downloaded {
@@ -509,11 +823,27 @@ pub async fn environment_import_add_repositories(
 ) -> Result<(), RustError> {
     let mut settings = settings.load_mut(io.inner()).await?;
     for adding_repo in repositories {
+        if adding_repo.public_key.is_none() && settings.require_signed_repositories() {
+            return Err(RustError::unrecoverable(
+                "this environment requires new repositories to be signed",
+            ));
+        }
+
+        let verified_raw = download_and_verify_for_add(
+            http.inner(),
+            &adding_repo.url,
+            &adding_repo.headers.0,
+            adding_repo.public_key.as_deref(),
+        )
+        .await?;
+
         add_remote_repo(
             &mut settings,
             adding_repo.url,
             None,
             adding_repo.headers.0,
+            adding_repo.public_key,
+            verified_raw,
             io.inner(),
             http.inner(),
         )
@@ -564,6 +894,84 @@ pub async fn environment_clear_package_cache(
     Ok(())
 }
 
+#[derive(Serialize, specta::Type, Clone)]
+#[serde(tag = "type")]
+pub enum TauriPackageCacheEntryStatus {
+    Ok,
+    HashMismatch,
+    Missing,
+    Repaired,
+    RepairFailed { message: String },
+}
+
+#[derive(Serialize, specta::Type, Clone)]
+pub struct TauriPackageCacheEntry {
+    id: String,
+    version: String,
+    status: TauriPackageCacheEntryStatus,
+}
+
+impl From<PackageCacheVerifyStatus> for TauriPackageCacheEntryStatus {
+    fn from(value: PackageCacheVerifyStatus) -> Self {
+        match value {
+            PackageCacheVerifyStatus::Ok => Self::Ok,
+            PackageCacheVerifyStatus::HashMismatch => Self::HashMismatch,
+            PackageCacheVerifyStatus::Missing => Self::Missing,
+            PackageCacheVerifyStatus::Repaired => Self::Repaired,
+            PackageCacheVerifyStatus::RepairFailed(message) => Self::RepairFailed { message },
+        }
+    }
+}
+
+// Local user packages have no declared hash, so they're skipped rather than
+// reported as mismatched.
+#[tauri::command]
+#[specta::specta]
+pub async fn environment_verify_package_cache(
+    packages: State<'_, PackagesState>,
+    settings: State<'_, SettingsState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    http: State<'_, reqwest::Client>,
+    window: Window,
+    channel: String,
+    repair: bool,
+) -> Result<AsyncCallResult<usize, Vec<TauriPackageCacheEntry>>, RustError> {
+    let settings = settings.load(io.inner()).await?;
+    let loaded = packages.load(&settings, io.inner(), http.inner()).await?;
+
+    async_command(channel, window.clone(), async move {
+        With::<usize>::continue_async(|ctx| async move {
+            let io = window.state::<DefaultEnvironmentIo>();
+            let http = window.state::<reqwest::Client>();
+            let counter = AtomicUsize::new(0);
+            let mut report = Vec::new();
+
+            for package in loaded.packages() {
+                if let Some(entry) =
+                    verify_one_package_cache_entry(package, repair, io.inner(), http.inner())
+                        .await?
+                {
+                    report.push(TauriPackageCacheEntry {
+                        id: entry.id,
+                        version: entry.version.to_string(),
+                        status: entry.status.into(),
+                    });
+                }
+
+                let count = counter.fetch_add(1, Ordering::Relaxed);
+                ctx.emit(count).unwrap();
+            }
+
+            if repair {
+                window.state::<PackagesState>().clear_cache();
+            }
+
+            Ok(report)
+        })
+    })
+    .await
+}
+
 #[derive(Serialize, specta::Type)]
 pub struct TauriUserPackage {
     path: String,
@@ -591,6 +999,108 @@ pub async fn environment_get_user_packages(
         .collect())
 }
 
+#[derive(Serialize, specta::Type)]
+pub enum TauriExportUserPackagesRepositoryResult {
+    NoFilePicked,
+    Success,
+}
+
+#[derive(Serialize)]
+struct UserPackagesRepositoryJson {
+    name: String,
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<Url>,
+    packages: IndexMap<String, UserPackagesRepositoryEntryJson>,
+}
+
+#[derive(Serialize)]
+struct UserPackagesRepositoryEntryJson {
+    versions: IndexMap<String, serde_json::Value>,
+}
+
+fn build_user_packages_repository(
+    name: &str,
+    base_url: Option<&Url>,
+    packages: IndexMap<String, IndexMap<String, serde_json::Value>>,
+) -> UserPackagesRepositoryJson {
+    UserPackagesRepositoryJson {
+        name: name.to_owned(),
+        id: format!("com.local.user-packages.{name}"),
+        url: base_url.cloned(),
+        packages: packages
+            .into_iter()
+            .map(|(id, versions)| (id, UserPackagesRepositoryEntryJson { versions }))
+            .collect(),
+    }
+}
+
+// Publishing each package is zip + sha256 work, so this reports progress per
+// package the same way `environment_import_download_repositories` does.
+#[tauri::command]
+#[specta::specta]
+pub async fn environment_export_user_packages_as_repository(
+    settings: State<'_, SettingsState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    window: Window,
+    channel: String,
+    repository_name: String,
+    base_url: Option<Url>,
+) -> Result<AsyncCallResult<usize, TauriExportUserPackagesRepositoryResult>, RustError> {
+    let Some(path) = window
+        .dialog()
+        .file()
+        .set_parent(&window)
+        .add_filter("JSON", &["json"])
+        .set_file_name("vpm-repository.json")
+        .blocking_save_file()
+    else {
+        return async_command(channel, window.clone(), async move {
+            With::<usize>::continue_async(|_ctx| async move {
+                Ok(TauriExportUserPackagesRepositoryResult::NoFilePicked)
+            })
+        })
+        .await;
+    };
+
+    let settings = settings.load(io.inner()).await?;
+    let user_packages = UserPackageCollection::load(&settings, io.inner()).await;
+
+    async_command(channel, window.clone(), async move {
+        With::<usize>::continue_async(|ctx| async move {
+            let counter = AtomicUsize::new(0);
+            let mut packages: IndexMap<String, IndexMap<String, serde_json::Value>> =
+                IndexMap::new();
+
+            for (package_path, package_json) in user_packages.packages() {
+                let manifest = export_user_package_version(
+                    package_path,
+                    package_json,
+                    base_url.as_ref(),
+                    &path,
+                )
+                .await?;
+
+                packages
+                    .entry(package_json.name().to_owned())
+                    .or_default()
+                    .insert(package_json.version().to_string(), manifest);
+
+                let count = counter.fetch_add(1, Ordering::Relaxed);
+                ctx.emit(count).unwrap();
+            }
+
+            let repository =
+                build_user_packages_repository(&repository_name, base_url.as_ref(), packages);
+
+            write(&path, serde_json::to_vec_pretty(&repository)?).await?;
+
+            Ok(TauriExportUserPackagesRepositoryResult::Success)
+        })
+    })
+    .await
+}
+
 #[derive(Serialize, specta::Type)]
 pub enum TauriAddUserPackageWithPickerResult {
     NoFolderSelected,
@@ -662,3 +1172,117 @@ pub async fn environment_remove_user_packages(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_verifying_key_roundtrips_valid_key() {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let encoded = base64::engine::general_purpose::STANDARD
+            .encode(signing_key.verifying_key().to_bytes());
+
+        assert!(decode_verifying_key(&encoded).is_ok());
+    }
+
+    #[test]
+    fn decode_verifying_key_rejects_wrong_length() {
+        let encoded = base64::engine::general_purpose::STANDARD.encode([0u8; 16]);
+
+        assert_eq!(
+            decode_verifying_key(&encoded).unwrap_err(),
+            "public key must be 32 bytes"
+        );
+    }
+
+    #[test]
+    fn decode_verifying_key_rejects_invalid_base64() {
+        assert!(decode_verifying_key("not valid base64!!").is_err());
+    }
+
+    #[tokio::test]
+    async fn download_and_verify_for_add_skips_network_without_a_pinned_key() {
+        let http = reqwest::Client::new();
+        let url: Url = "https://example.com/repo.json".parse().unwrap();
+        let headers = IndexMap::new();
+
+        assert_eq!(
+            download_and_verify_for_add(&http, &url, &headers, None)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn download_result_or_error_converts_err_to_download_error() {
+        let result: Result<TauriDownloadRepository, RustError> =
+            Err(RustError::unrecoverable("boom"));
+
+        assert!(matches!(
+            download_result_or_error(result),
+            TauriDownloadRepository::DownloadError { message } if message.contains("boom")
+        ));
+    }
+
+    #[test]
+    fn download_result_or_error_passes_through_ok() {
+        let result: Result<TauriDownloadRepository, RustError> =
+            Ok(TauriDownloadRepository::Duplicated);
+
+        assert!(matches!(
+            download_result_or_error(result),
+            TauriDownloadRepository::Duplicated
+        ));
+    }
+
+    #[test]
+    fn validators_cache_path_is_alongside_the_repository_cache_file() {
+        let local_path = Path::new("Repos/com.example.repo.json");
+
+        assert_eq!(
+            validators_cache_path(local_path),
+            Path::new("Repos/com.example.repo.validators.json")
+        );
+    }
+
+    #[test]
+    fn package_cache_entry_status_maps_ok_and_repair_failed() {
+        assert!(matches!(
+            TauriPackageCacheEntryStatus::from(PackageCacheVerifyStatus::Ok),
+            TauriPackageCacheEntryStatus::Ok
+        ));
+
+        assert!(matches!(
+            TauriPackageCacheEntryStatus::from(PackageCacheVerifyStatus::RepairFailed(
+                "x".to_owned()
+            )),
+            TauriPackageCacheEntryStatus::RepairFailed { message } if message == "x"
+        ));
+    }
+
+    #[test]
+    fn build_user_packages_repository_groups_versions_by_package_id() {
+        let mut versions = IndexMap::new();
+        versions.insert(
+            "1.0.0".to_owned(),
+            serde_json::json!({"name": "com.example.pkg"}),
+        );
+
+        let mut packages = IndexMap::new();
+        packages.insert("com.example.pkg".to_owned(), versions);
+
+        let repository = build_user_packages_repository("My Packages", None, packages);
+
+        assert_eq!(repository.name, "My Packages");
+        assert!(repository.url.is_none());
+        assert_eq!(
+            repository.packages["com.example.pkg"]
+                .versions
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["1.0.0"]
+        );
+    }
+}